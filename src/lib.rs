@@ -27,31 +27,67 @@
 //! * `futures` (default) Implement `AsyncWrite` and `AsyncRead` from `futures::io`
 //! * `tokio` Implement `AsyncWrite` and `AsyncRead` from `tokio::io`.
 
-use state::State;
+use state::{State, BUFFER_SIZE};
+use std::cell::Cell;
 use std::sync::{Arc, Mutex};
 
 pub use self::reader::PipeReader;
 pub use self::writer::PipeWriter;
 
+#[cfg(feature = "tokio")]
+pub use self::sync::{SyncPipeReader, SyncPipeWriter};
+
+#[cfg(feature = "futures")]
+pub use self::stream::{ReaderStream, SinkWriter};
+
 mod reader;
 mod state;
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(feature = "tokio")]
+mod sync;
 mod writer;
 
 /// Creates a piped pair of an [`AsyncWrite`](https://docs.rs/tokio/1.9.0/tokio/io/trait.AsyncWrite.html) and an [`AsyncRead`](https://docs.rs/tokio/1.9.0/tokio/io/trait.AsyncRead.html).
+///
+/// The pipe buffers up to `1024` bytes before the writer is parked; use [`pipe_with_capacity`] to
+/// pick a different threshold.
 pub fn pipe() -> (PipeWriter, PipeReader) {
-    let shared_state = Arc::new(Mutex::new(State {
-        reader_waker: None,
-        writer_waker: None,
-        closed: false,
-        buffer: Vec::new(),
-    }));
+    pipe_with_capacity(BUFFER_SIZE)
+}
+
+/// Creates a piped pair whose buffer holds up to `cap` bytes before the writer is parked for
+/// backpressure.
+///
+/// `cap` is floored to `1`: a zero-capacity bounded buffer could never admit a byte, so
+/// `pipe_with_capacity(0)` silently behaves like `pipe_with_capacity(1)` rather than deadlocking
+/// every write.
+pub fn pipe_with_capacity(cap: usize) -> (PipeWriter, PipeReader) {
+    new_pipe(State::with_capacity(cap, true))
+}
+
+/// Creates an unbounded piped pair whose buffer grows to hold everything written, so the writer is
+/// never parked on a full buffer.
+///
+/// This trades memory for never blocking the producer: each write is accepted in full and the
+/// backing buffer is enlarged as needed, à la tokio's `unbounded_channel`. Prefer [`pipe`] or
+/// [`pipe_with_capacity`] when you want backpressure to bound memory use.
+pub fn pipe_unbounded() -> (PipeWriter, PipeReader) {
+    new_pipe(State::with_capacity(BUFFER_SIZE, false))
+}
+
+fn new_pipe(state: State) -> (PipeWriter, PipeReader) {
+    let shared_state = Arc::new(Mutex::new(state));
 
     let w = PipeWriter {
         state: shared_state.clone(),
+        shutdown: Cell::new(false),
     };
 
     let r = PipeReader {
         state: shared_state,
+        fill: Vec::new(),
+        fill_pos: 0,
     };
 
     (w, r)
@@ -121,5 +157,189 @@ mod test {
             drop(reader);
             write_handle.await.unwrap();
         }
+
+        #[tokio::test]
+        async fn ring_wraps_around_under_small_capacity() {
+            let (mut writer, mut reader) = pipe_with_capacity(4);
+            let data: Vec<u8> = (0..64u8).collect();
+
+            let expected = data.clone();
+            let write_handle = tokio::spawn(async move {
+                writer.write_all(&data).await.unwrap();
+            });
+
+            let mut read_buf = Vec::new();
+            reader.read_to_end(&mut read_buf).await.unwrap();
+            write_handle.await.unwrap();
+
+            assert_eq!(read_buf, expected);
+        }
+
+        #[tokio::test]
+        async fn unbounded_accepts_more_than_capacity_without_reader() {
+            let (mut writer, mut reader) = pipe_unbounded();
+            let data = vec![7u8; BUFFER_SIZE * 4];
+
+            // No reader is draining, yet the write completes because the buffer grows to fit it.
+            writer.write_all(&data).await.unwrap();
+            writer.shutdown().await.unwrap();
+
+            let mut read_buf = Vec::new();
+            reader.read_to_end(&mut read_buf).await.unwrap();
+            assert_eq!(read_buf, data);
+        }
+
+        #[tokio::test]
+        async fn write_vectored_across_slices() {
+            use std::io::IoSlice;
+
+            let (mut writer, mut reader) = pipe();
+            let write_handle = tokio::spawn(async move {
+                let bufs = [IoSlice::new(b"hello "), IoSlice::new(b"world")];
+                let written = writer.write_vectored(&bufs).await.unwrap();
+                assert_eq!(written, 11);
+                writer.shutdown().await.unwrap();
+            });
+
+            let mut read_buf = Vec::new();
+            reader.read_to_end(&mut read_buf).await.unwrap();
+            write_handle.await.unwrap();
+
+            assert_eq!(&read_buf, b"hello world");
+        }
+
+        #[tokio::test]
+        async fn read_lines_via_async_buf_read() {
+            use tokio::io::AsyncBufReadExt;
+
+            let (mut writer, mut reader) = pipe();
+            let write_handle = tokio::spawn(async move {
+                writer.write_all(b"first\nsecond\n").await.unwrap();
+                writer.shutdown().await.unwrap();
+            });
+
+            let mut first = String::new();
+            reader.read_line(&mut first).await.unwrap();
+            assert_eq!(first, "first\n");
+
+            let mut rest = Vec::new();
+            reader.read_to_end(&mut rest).await.unwrap();
+            write_handle.await.unwrap();
+            assert_eq!(&rest, b"second\n");
+        }
+
+        #[tokio::test]
+        async fn broken_pipe_when_reader_is_closed() {
+            let (mut writer, reader) = pipe();
+            reader.close().unwrap();
+            let io_error = writer.write_all(&[0u8; 8]).await.unwrap_err();
+            assert_eq!(io_error.kind(), io::ErrorKind::BrokenPipe);
+        }
+
+        #[tokio::test]
+        async fn sync_bridge_round_trip() {
+            use std::io::{Read, Write};
+
+            let (writer, reader) = pipe();
+            let handle = tokio::runtime::Handle::current();
+
+            let mut sync_writer = writer.into_sync(handle.clone());
+            let write_handle = tokio::task::spawn_blocking(move || {
+                sync_writer.write_all(b"sync bytes").unwrap();
+                sync_writer.flush().unwrap();
+            });
+
+            let mut sync_reader = reader.into_sync(handle);
+            let read_handle = tokio::task::spawn_blocking(move || {
+                let mut buf = vec![0u8; 10];
+                sync_reader.read_exact(&mut buf).unwrap();
+                buf
+            });
+
+            write_handle.await.unwrap();
+            assert_eq!(read_handle.await.unwrap(), b"sync bytes");
+        }
+
+        #[tokio::test]
+        async fn multi_writer_fan_in_with_backpressure() {
+            // A tiny bounded buffer forces both writers to park on a full buffer at the same time;
+            // a single waker slot would strand one of them forever.
+            let (writer, mut reader) = pipe_with_capacity(4);
+            let writer2 = writer.clone();
+
+            let w1 = tokio::spawn(async move {
+                let mut w = writer;
+                w.write_all(&[1u8; 1000]).await.unwrap();
+            });
+            let w2 = tokio::spawn(async move {
+                let mut w = writer2;
+                w.write_all(&[2u8; 1000]).await.unwrap();
+            });
+
+            let mut read_buf = Vec::new();
+            reader.read_to_end(&mut read_buf).await.unwrap();
+            w1.await.unwrap();
+            w2.await.unwrap();
+
+            assert_eq!(read_buf.len(), 2000);
+            assert_eq!(read_buf.iter().filter(|&&b| b == 1).count(), 1000);
+            assert_eq!(read_buf.iter().filter(|&&b| b == 2).count(), 1000);
+        }
+
+        #[tokio::test]
+        async fn closing_one_writer_does_not_eof_the_others() {
+            // A tiny bounded buffer forces the survivor to park mid-write; if `close` on `w1`
+            // mistakenly EOFed the reader, `w2` would be stranded parked forever instead of
+            // draining.
+            let (writer, mut reader) = pipe_with_capacity(8);
+            let writer2 = writer.clone();
+
+            let mut w1 = writer;
+            w1.write_all(b"hi").await.unwrap();
+            w1.shutdown().await.unwrap();
+            drop(w1);
+
+            let w2 = tokio::spawn(async move {
+                let mut w = writer2;
+                w.write_all(&[9u8; 64]).await.unwrap();
+                w.shutdown().await.unwrap();
+            });
+
+            let mut read_buf = Vec::new();
+            reader.read_to_end(&mut read_buf).await.unwrap();
+            w2.await.unwrap();
+
+            assert_eq!(read_buf.len(), 66);
+            assert_eq!(&read_buf[..2], b"hi");
+            assert!(read_buf[2..].iter().all(|&b| b == 9));
+        }
+    }
+
+    #[cfg(all(feature = "futures", feature = "tokio"))]
+    mod test_futures {
+        use crate::*;
+        use bytes::Bytes;
+        use futures::sink::SinkExt;
+        use futures::stream::StreamExt;
+
+        #[tokio::test]
+        async fn sink_stream_round_trip() {
+            let (writer, reader) = pipe();
+            let mut sink = writer.into_sink();
+            let mut stream = reader.into_stream();
+
+            let send_handle = tokio::spawn(async move {
+                sink.send(Bytes::from_static(b"chunked")).await.unwrap();
+                sink.close().await.unwrap();
+            });
+
+            let mut received = Vec::new();
+            while let Some(chunk) = stream.next().await {
+                received.extend_from_slice(&chunk.unwrap());
+            }
+            send_handle.await.unwrap();
+
+            assert_eq!(&received, b"chunked");
+        }
     }
 }
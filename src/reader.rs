@@ -14,19 +14,26 @@ use std::task::{Context, Poll};
 /// [tokio-async-read]: https://docs.rs/tokio/1.9.0/tokio/io/trait.AsyncRead.html
 pub struct PipeReader {
     pub(crate) state: Arc<Mutex<State>>,
+    /// Staging area for the [`AsyncBufRead`] impl. The ring lives behind the shared `Mutex`, so a
+    /// borrow cannot outlive the lock guard; `poll_fill_buf` instead copies the contiguous run at
+    /// `head` into this owned buffer and hands that out by reference. The allocation is reused
+    /// across fills (only growing when a run is longer than any seen before), so the consumer still
+    /// needs no buffer of its own, but this is a staged copy rather than a view into the ring.
+    pub(crate) fill: Vec<u8>,
+    pub(crate) fill_pos: usize,
 }
 
 impl PipeReader {
-    /// Closes the pipe, any further read will return EOF and any further write will raise an error.
+    /// Shuts down the read half: the reader is done consuming, so any further write will raise a
+    /// `BrokenPipe` error. The writer half can still signal EOF independently.
     pub fn close(&self) -> io::Result<()> {
         match self.state.lock() {
             Ok(mut state) => {
-                state.closed = true;
-                self.wake_writer_half(&*state);
+                state.read_shutdown = true;
+                state.wake_writers();
                 Ok(())
             }
-            Err(err) => Err(io::Error::new(
-                io::ErrorKind::Other,
+            Err(err) => Err(io::Error::other(
                 format!(
                     "{}: PipeReader: Failed to lock the channel state: {}",
                     env!("CARGO_PKG_NAME"),
@@ -41,8 +48,7 @@ impl PipeReader {
         let state = match self.state.lock() {
             Ok(s) => s,
             Err(err) => {
-                return Err(io::Error::new(
-                    io::ErrorKind::Other,
+                return Err(io::Error::other(
                     format!(
                         "{}: PipeReader: Failed to lock the channel state: {}",
                         env!("CARGO_PKG_NAME"),
@@ -52,13 +58,38 @@ impl PipeReader {
             }
         };
 
-        Ok(state.buffer.is_empty())
+        Ok(state.len == 0 && self.fill_pos >= self.fill.len())
     }
 
-    fn wake_writer_half(&self, state: &State) {
-        if let Some(ref waker) = state.writer_waker {
-            waker.clone().wake();
+    /// Drains any bytes still staged by [`AsyncBufRead`] into `dst`, returning the count moved.
+    ///
+    /// Scalar and vectored reads consult this first so that interleaving `read*` with
+    /// `fill_buf`/`consume` never strands bytes that were pulled out of the ring into the staging
+    /// buffer.
+    fn drain_staged(&mut self, dst: &mut [u8]) -> usize {
+        if self.fill_pos >= self.fill.len() {
+            return 0;
         }
+        let staged = &self.fill[self.fill_pos..];
+        let count = staged.len().min(dst.len());
+        dst[..count].copy_from_slice(&staged[..count]);
+        self.fill_pos += count;
+        count
+    }
+
+    /// Converts this async read half into a blocking [`std::io::Read`] bridge driven on the given
+    /// [`tokio::runtime::Handle`]. Move the bridge into [`tokio::task::spawn_blocking`] to feed the
+    /// pipe to synchronous code while the write half stays fully async.
+    #[cfg(feature = "tokio")]
+    pub fn into_sync(self, handle: tokio::runtime::Handle) -> crate::SyncPipeReader {
+        crate::SyncPipeReader::new(self, handle)
+    }
+
+    /// Converts this read half into a [`Stream`](futures::stream::Stream) of
+    /// [`Bytes`](bytes::Bytes) chunks, yielding each drained buffer chunk until EOF.
+    #[cfg(feature = "futures")]
+    pub fn into_stream(self) -> crate::ReaderStream {
+        crate::ReaderStream::new(self)
     }
 
     fn poll_read(
@@ -66,11 +97,17 @@ impl PipeReader {
         cx: &mut Context,
         buf: &mut [u8],
     ) -> Poll<io::Result<usize>> {
-        let mut state = match self.state.lock() {
+        let this = self.get_mut();
+
+        let staged = this.drain_staged(buf);
+        if staged > 0 {
+            return Poll::Ready(Ok(staged));
+        }
+
+        let mut state = match this.state.lock() {
             Ok(s) => s,
             Err(err) => {
-                return Poll::Ready(Err(io::Error::new(
-                    io::ErrorKind::Other,
+                return Poll::Ready(Err(io::Error::other(
                     format!(
                         "{}: PipeReader: Failed to lock the channel state: {}",
                         env!("CARGO_PKG_NAME"),
@@ -80,24 +117,137 @@ impl PipeReader {
             }
         };
 
-        if state.buffer.is_empty() {
-            if state.closed || Arc::strong_count(&self.state) == 1 {
+        if state.len == 0 {
+            if state.all_writers_shut_down() {
                 Poll::Ready(Ok(0))
             } else {
-                self.wake_writer_half(&*state);
-                state.reader_waker = Some(cx.waker().clone());
+                state.wake_writers();
+                state.park_reader(cx.waker());
                 Poll::Pending
             }
         } else {
-            self.wake_writer_half(&*state);
-            let size_to_read = state.buffer.len().min(buf.len());
-            let (to_read, rest) = state.buffer.split_at(size_to_read);
-            buf[..size_to_read].copy_from_slice(to_read);
-            state.buffer = rest.to_vec();
+            state.wake_writers();
+            let size_to_read = state.read(buf);
 
             Poll::Ready(Ok(size_to_read))
         }
     }
+
+    // Only `futures::io::AsyncRead` declares a vectored-read hook; `tokio::io::AsyncRead` has
+    // none, so this would sit unused (and fail `-D warnings`) under `--features tokio` alone.
+    #[cfg(feature = "futures")]
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        bufs: &mut [io::IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        {
+            let mut staged = 0;
+            for buf in bufs.iter_mut() {
+                if this.fill_pos >= this.fill.len() {
+                    break;
+                }
+                staged += this.drain_staged(&mut buf[..]);
+            }
+            if staged > 0 {
+                return Poll::Ready(Ok(staged));
+            }
+        }
+
+        let mut state = match this.state.lock() {
+            Ok(s) => s,
+            Err(err) => {
+                return Poll::Ready(Err(io::Error::other(
+                    format!(
+                        "{}: PipeReader: Failed to lock the channel state: {}",
+                        env!("CARGO_PKG_NAME"),
+                        err
+                    ),
+                )))
+            }
+        };
+
+        if state.len == 0 {
+            if state.all_writers_shut_down() {
+                Poll::Ready(Ok(0))
+            } else {
+                state.wake_writers();
+                state.park_reader(cx.waker());
+                Poll::Pending
+            }
+        } else {
+            state.wake_writers();
+            let mut read = 0;
+            for buf in bufs {
+                if state.len == 0 {
+                    break;
+                }
+                read += state.read(&mut buf[..]);
+            }
+
+            Poll::Ready(Ok(read))
+        }
+    }
+
+    /// Note this is *not* zero-copy: it cannot return a `&[u8]` borrowed straight out of
+    /// `state.buffer`, because that buffer lives behind the shared `Mutex` and the returned slice
+    /// would have to outlive the lock guard. What it avoids is a *consumer-side* buffer — callers
+    /// get a reference without supplying their own `Vec` — at the cost of one copy into `self.fill`
+    /// per ring run. A true zero-copy `AsyncBufRead` would need the ring itself restructured (e.g.
+    /// `Arc`-shared immutable chunks) rather than a single mutex-guarded backing array.
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+
+        // Hand back whatever is left of the previously staged run before touching the ring again.
+        if this.fill_pos < this.fill.len() {
+            return Poll::Ready(Ok(&this.fill[this.fill_pos..]));
+        }
+
+        let mut state = match this.state.lock() {
+            Ok(s) => s,
+            Err(err) => {
+                return Poll::Ready(Err(io::Error::other(
+                    format!(
+                        "{}: PipeReader: Failed to lock the channel state: {}",
+                        env!("CARGO_PKG_NAME"),
+                        err
+                    ),
+                )))
+            }
+        };
+
+        if state.len == 0 {
+            return if state.all_writers_shut_down() {
+                Poll::Ready(Ok(&[]))
+            } else {
+                state.wake_writers();
+                state.park_reader(cx.waker());
+                Poll::Pending
+            };
+        }
+
+        state.wake_writers();
+
+        // Copy the contiguous run at `head` into the staging buffer and advance the ring past it.
+        let cap = state.buffer.len();
+        let contiguous = state.len.min(cap - state.head);
+        this.fill.clear();
+        this.fill
+            .extend_from_slice(&state.buffer[state.head..state.head + contiguous]);
+        state.head = (state.head + contiguous) % cap;
+        state.len -= contiguous;
+        this.fill_pos = 0;
+        drop(state);
+
+        Poll::Ready(Ok(&this.fill[..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.fill_pos = (this.fill_pos + amt).min(this.fill.len());
+    }
 }
 
 #[cfg(feature = "tokio")]
@@ -123,4 +273,60 @@ impl futures::io::AsyncRead for PipeReader {
     ) -> Poll<io::Result<usize>> {
         self.poll_read(cx, buf)
     }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        bufs: &mut [io::IoSliceMut<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_read_vectored(cx, bufs)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncBufRead for PipeReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<&[u8]>> {
+        self.poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.consume(amt)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl futures::io::AsyncBufRead for PipeReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<&[u8]>> {
+        self.poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.consume(amt)
+    }
+}
+
+impl Clone for PipeReader {
+    fn clone(&self) -> PipeReader {
+        // Recover from a poisoned lock so the count stays balanced against `Drop`.
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        state.reader_count += 1;
+        drop(state);
+
+        PipeReader {
+            state: self.state.clone(),
+            fill: Vec::new(),
+            fill_pos: 0,
+        }
+    }
+}
+
+impl Drop for PipeReader {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        state.reader_count -= 1;
+        if state.reader_count == 0 {
+            // The last reader is gone, so wake the writers to let them observe BrokenPipe.
+            state.wake_writers();
+        }
+    }
 }
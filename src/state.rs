@@ -3,8 +3,147 @@ use std::task::Waker;
 pub const BUFFER_SIZE: usize = 1024;
 
 pub(crate) struct State {
-    pub(crate) reader_waker: Option<Waker>,
-    pub(crate) writer_waker: Option<Waker>,
-    pub(crate) closed: bool,
-    pub(crate) buffer: Vec<u8>,
+    /// Every parked reader half waiting for bytes to arrive. A single slot would lose wake-ups once
+    /// [`PipeReader`](crate::PipeReader) is cloneable, so each parked half is tracked and woken.
+    pub(crate) reader_wakers: Vec<Waker>,
+    /// Every parked writer half waiting for room to free up. Several fan-in writers can block on a
+    /// full bounded buffer at once, so all of them are tracked and woken when the reader drains.
+    pub(crate) writer_wakers: Vec<Waker>,
+    /// Number of live writer halves that have individually signalled EOF via
+    /// [`PipeWriter::close`](crate::PipeWriter::close). A single writer closing must not end the
+    /// stream while sibling writers are still producing (MPSC fan-in), so the reader only reaches
+    /// EOF once this equals `writer_count` — i.e. every writer still alive has closed (or there are
+    /// none left at all).
+    pub(crate) writer_shutdown_count: usize,
+    /// Set when the reader half has gone away: further writes fault with `BrokenPipe`.
+    pub(crate) read_shutdown: bool,
+    /// Number of live [`PipeReader`](crate::PipeReader) halves; once this hits zero, writes fault
+    /// with `BrokenPipe` (as they also do when `read_shutdown` is set).
+    pub(crate) reader_count: usize,
+    /// Number of live [`PipeWriter`](crate::PipeWriter) halves; the reader sees EOF once the buffer
+    /// is drained and this equals `writer_shutdown_count` (every live writer closed, or none left).
+    pub(crate) writer_count: usize,
+    pub(crate) buffer: Box<[u8]>,
+    pub(crate) head: usize,
+    pub(crate) tail: usize,
+    pub(crate) len: usize,
+    /// When `false` the buffer grows to hold every written byte and the writer never parks on a
+    /// full buffer; when `true` the writer is parked once `remaining() == 0`.
+    pub(crate) bounded: bool,
+}
+
+impl State {
+    pub(crate) fn with_capacity(cap: usize, bounded: bool) -> State {
+        // A zero-capacity bounded buffer could never make progress, so keep at least one slot.
+        let cap = cap.max(1);
+        State {
+            reader_wakers: Vec::new(),
+            writer_wakers: Vec::new(),
+            writer_shutdown_count: 0,
+            read_shutdown: false,
+            reader_count: 1,
+            writer_count: 1,
+            buffer: vec![0u8; cap].into_boxed_slice(),
+            head: 0,
+            tail: 0,
+            len: 0,
+            bounded,
+        }
+    }
+
+    /// Grows the backing buffer so that at least `needed` more bytes fit, re-laying the current
+    /// contents out contiguously from index `0`. Only used by unbounded pipes.
+    pub(crate) fn grow(&mut self, needed: usize) {
+        if self.remaining() >= needed {
+            return;
+        }
+
+        let cap = self.buffer.len();
+        let mut new_cap = cap.max(1);
+        while new_cap - self.len < needed {
+            new_cap *= 2;
+        }
+
+        let existing = self.len;
+        let mut new_buffer = vec![0u8; new_cap].into_boxed_slice();
+        let first = existing.min(cap - self.head);
+        new_buffer[..first].copy_from_slice(&self.buffer[self.head..self.head + first]);
+        if first < existing {
+            new_buffer[first..existing].copy_from_slice(&self.buffer[..existing - first]);
+        }
+
+        self.buffer = new_buffer;
+        self.head = 0;
+        self.tail = existing;
+    }
+
+    /// Parks `waker` as a reader waiting for bytes, de-duplicating against the wakers already stored
+    /// so a half that is re-polled before it makes progress does not grow the list unboundedly.
+    pub(crate) fn park_reader(&mut self, waker: &Waker) {
+        if !self.reader_wakers.iter().any(|w| w.will_wake(waker)) {
+            self.reader_wakers.push(waker.clone());
+        }
+    }
+
+    /// Parks `waker` as a writer waiting for free space, de-duplicating as [`park_reader`](Self::park_reader) does.
+    pub(crate) fn park_writer(&mut self, waker: &Waker) {
+        if !self.writer_wakers.iter().any(|w| w.will_wake(waker)) {
+            self.writer_wakers.push(waker.clone());
+        }
+    }
+
+    /// Wakes and clears every parked reader half; each re-registers on its next poll if still blocked.
+    pub(crate) fn wake_readers(&mut self) {
+        for waker in self.reader_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Wakes and clears every parked writer half; each re-registers on its next poll if still blocked.
+    pub(crate) fn wake_writers(&mut self) {
+        for waker in self.writer_wakers.drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// The number of bytes that can still be written before the buffer is full.
+    pub(crate) fn remaining(&self) -> usize {
+        self.buffer.len() - self.len
+    }
+
+    /// True once every writer half still alive has closed (or none are left at all), meaning no
+    /// more bytes can ever arrive and the reader should see EOF once the buffer drains.
+    pub(crate) fn all_writers_shut_down(&self) -> bool {
+        self.writer_count == self.writer_shutdown_count
+    }
+
+    /// Copies up to `dst.len()` buffered bytes into `dst`, advancing the read position and handling
+    /// wrap-around. Returns the number of bytes copied.
+    pub(crate) fn read(&mut self, dst: &mut [u8]) -> usize {
+        let count = self.len.min(dst.len());
+        let cap = self.buffer.len();
+        let first = count.min(cap - self.head);
+        dst[..first].copy_from_slice(&self.buffer[self.head..self.head + first]);
+        if first < count {
+            dst[first..count].copy_from_slice(&self.buffer[..count - first]);
+        }
+        self.head = (self.head + count) % cap;
+        self.len -= count;
+        count
+    }
+
+    /// Copies as much of `src` as fits into the free region of the buffer, advancing the write
+    /// position and handling wrap-around. Returns the number of bytes copied.
+    pub(crate) fn write(&mut self, src: &[u8]) -> usize {
+        let count = self.remaining().min(src.len());
+        let cap = self.buffer.len();
+        let first = count.min(cap - self.tail);
+        self.buffer[self.tail..self.tail + first].copy_from_slice(&src[..first]);
+        if first < count {
+            self.buffer[..count - first].copy_from_slice(&src[first..count]);
+        }
+        self.tail = (self.tail + count) % cap;
+        self.len += count;
+        count
+    }
 }
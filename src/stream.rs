@@ -0,0 +1,133 @@
+use crate::state::BUFFER_SIZE;
+use crate::{PipeReader, PipeWriter};
+use bytes::{Buf, Bytes};
+use futures::io::{AsyncRead, AsyncWrite};
+use futures::sink::Sink;
+use futures::stream::Stream;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A [`Stream`] of [`Bytes`] chunks drained from a [`PipeReader`].
+///
+/// Created by [`PipeReader::into_stream`]. Each poll yields the next buffer chunk the reader hands
+/// out, and the stream ends once the writer half signals EOF. This lets the pipe feed
+/// `StreamExt`/codec layers that consume `Bytes` rather than raw `poll_read` loops.
+pub struct ReaderStream {
+    reader: PipeReader,
+    /// Read scratch reused across polls, so a spurious wake-up that yields no bytes costs no
+    /// allocation. Sized to the pipe's own capacity rather than a fixed chunk size.
+    buf: Vec<u8>,
+}
+
+impl ReaderStream {
+    pub(crate) fn new(reader: PipeReader) -> ReaderStream {
+        // Match the scratch to the pipe's capacity at conversion time so a full buffer drains in
+        // one chunk (an unbounded pipe that grows later drains in several); fall back to
+        // `BUFFER_SIZE` if the state lock is poisoned.
+        let capacity = reader
+            .state
+            .lock()
+            .map(|state| state.buffer.len())
+            .unwrap_or(BUFFER_SIZE);
+        ReaderStream {
+            reader,
+            buf: vec![0u8; capacity],
+        }
+    }
+
+    /// Unwraps this adapter back into the [`PipeReader`].
+    pub fn into_inner(self) -> PipeReader {
+        self.reader
+    }
+}
+
+impl Stream for ReaderStream {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        match Pin::new(&mut this.reader).poll_read(cx, &mut this.buf) {
+            Poll::Ready(Ok(0)) => Poll::Ready(None),
+            Poll::Ready(Ok(n)) => Poll::Ready(Some(Ok(Bytes::copy_from_slice(&this.buf[..n])))),
+            Poll::Ready(Err(err)) => Poll::Ready(Some(Err(err))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A [`Sink`] of [`Bytes`] chunks that writes each item into a [`PipeWriter`].
+///
+/// Created by [`PipeWriter::into_sink`]. Items are written (and flushed on `poll_flush`/
+/// `poll_close`) into the pipe, so the writer composes with `SinkExt` combinators and codec layers
+/// that produce `Bytes`.
+pub struct SinkWriter {
+    writer: PipeWriter,
+    buf: Bytes,
+}
+
+impl SinkWriter {
+    pub(crate) fn new(writer: PipeWriter) -> SinkWriter {
+        SinkWriter {
+            writer,
+            buf: Bytes::new(),
+        }
+    }
+
+    /// Unwraps this adapter back into the [`PipeWriter`].
+    pub fn into_inner(self) -> PipeWriter {
+        self.writer
+    }
+
+    /// Drives the pending chunk into the pipe until it is fully written.
+    fn poll_write_buf(&mut self, cx: &mut Context) -> Poll<io::Result<()>> {
+        while !self.buf.is_empty() {
+            match Pin::new(&mut self.writer).poll_write(cx, &self.buf) {
+                Poll::Ready(Ok(0)) => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        format!(
+                            "{}: SinkWriter: Failed to write the whole chunk",
+                            env!("CARGO_PKG_NAME")
+                        ),
+                    )))
+                }
+                Poll::Ready(Ok(n)) => self.buf.advance(n),
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Sink<Bytes> for SinkWriter {
+    type Error = io::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_write_buf(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Bytes) -> Result<(), Self::Error> {
+        self.get_mut().buf = item;
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        match this.poll_write_buf(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.writer).poll_flush(cx),
+            other => other,
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        match this.poll_write_buf(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.writer).poll_close(cx),
+            other => other,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{PipeReader, PipeWriter};
+use std::io::{self, Read, Write};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::runtime::Handle;
+
+/// A blocking [`std::io::Read`] view over a [`PipeReader`].
+///
+/// Created by [`PipeReader::into_sync`]. Each call drives the async [`PipeReader`] to completion on
+/// the captured [`Handle`] via [`Handle::block_on`], so it must be used from a blocking context
+/// (for example a thread spawned with [`tokio::task::spawn_blocking`]) rather than from inside an
+/// async task, otherwise `block_on` will panic.
+pub struct SyncPipeReader {
+    reader: PipeReader,
+    handle: Handle,
+}
+
+impl SyncPipeReader {
+    pub(crate) fn new(reader: PipeReader, handle: Handle) -> SyncPipeReader {
+        SyncPipeReader { reader, handle }
+    }
+
+    /// Unwraps this bridge back into the async [`PipeReader`].
+    pub fn into_async(self) -> PipeReader {
+        self.reader
+    }
+}
+
+impl Read for SyncPipeReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // A zero-length read must return immediately rather than parking the thread on `block_on`.
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let SyncPipeReader { reader, handle } = self;
+        handle.block_on(reader.read(buf))
+    }
+}
+
+/// A blocking [`std::io::Write`] view over a [`PipeWriter`].
+///
+/// Created by [`PipeWriter::into_sync`]. Each call drives the async [`PipeWriter`] to completion on
+/// the captured [`Handle`] via [`Handle::block_on`], so it must be used from a blocking context
+/// (for example a thread spawned with [`tokio::task::spawn_blocking`]) rather than from inside an
+/// async task, otherwise `block_on` will panic.
+pub struct SyncPipeWriter {
+    writer: PipeWriter,
+    handle: Handle,
+}
+
+impl SyncPipeWriter {
+    pub(crate) fn new(writer: PipeWriter, handle: Handle) -> SyncPipeWriter {
+        SyncPipeWriter { writer, handle }
+    }
+
+    /// Unwraps this bridge back into the async [`PipeWriter`].
+    pub fn into_async(self) -> PipeWriter {
+        self.writer
+    }
+}
+
+impl Write for SyncPipeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let SyncPipeWriter { writer, handle } = self;
+        handle.block_on(writer.write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let SyncPipeWriter { writer, handle } = self;
+        handle.block_on(writer.flush())
+    }
+}
@@ -1,4 +1,5 @@
-use crate::state::{State, BUFFER_SIZE};
+use crate::state::State;
+use std::cell::Cell;
 use std::io;
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
@@ -14,19 +15,29 @@ use std::task::{Context, Poll};
 /// [tokio-async-write]: https://docs.rs/tokio/1.9.0/tokio/io/trait.AsyncWrite.html
 pub struct PipeWriter {
     pub(crate) state: Arc<Mutex<State>>,
+    /// Whether this particular writer half has already called [`close`](Self::close). Several
+    /// writers can be cloned onto the same pipe (MPSC fan-in), so one writer closing must not
+    /// count towards EOF more than once, and must not be mistaken for its siblings closing too;
+    /// this flag lets `close` and `Drop` keep `State::writer_shutdown_count` in sync with exactly
+    /// the writers that have signalled they're done.
+    pub(crate) shutdown: Cell<bool>,
 }
 
 impl PipeWriter {
-    /// Closes the pipe, any further read will return EOF and any further write will raise an error.
+    /// Shuts down the write half, signalling EOF for this writer: once every other writer half
+    /// still alive has also closed (or dropped), the reader drains any buffered bytes and then
+    /// sees end-of-stream. The read half can still be shut down independently. Idempotent: calling
+    /// this more than once on the same half only counts once.
     pub fn close(&self) -> io::Result<()> {
         match self.state.lock() {
             Ok(mut state) => {
-                state.closed = true;
-                self.wake_reader_half(&*state);
+                if !self.shutdown.replace(true) {
+                    state.writer_shutdown_count += 1;
+                }
+                state.wake_readers();
                 Ok(())
             }
-            Err(err) => Err(io::Error::new(
-                io::ErrorKind::Other,
+            Err(err) => Err(io::Error::other(
                 format!(
                     "{}: PipeWriter: Failed to lock the channel state: {}",
                     env!("CARGO_PKG_NAME"),
@@ -41,8 +52,7 @@ impl PipeWriter {
         let state = match self.state.lock() {
             Ok(s) => s,
             Err(err) => {
-                return Err(io::Error::new(
-                    io::ErrorKind::Other,
+                return Err(io::Error::other(
                     format!(
                         "{}: PipeWriter: Failed to lock the channel state: {}",
                         env!("CARGO_PKG_NAME"),
@@ -52,31 +62,72 @@ impl PipeWriter {
             }
         };
 
-        Ok(state.buffer.is_empty())
+        Ok(state.len == 0)
     }
 
-    fn wake_reader_half(&self, state: &State) {
-        if let Some(ref waker) = state.reader_waker {
-            waker.clone().wake();
-        }
+    /// Converts this async write half into a blocking [`std::io::Write`] bridge driven on the given
+    /// [`tokio::runtime::Handle`]. Move the bridge into [`tokio::task::spawn_blocking`] to feed the
+    /// pipe from synchronous code while the read half stays fully async.
+    #[cfg(feature = "tokio")]
+    pub fn into_sync(self, handle: tokio::runtime::Handle) -> crate::SyncPipeWriter {
+        crate::SyncPipeWriter::new(self, handle)
+    }
+
+    /// Converts this write half into a [`Sink`](futures::sink::Sink) of [`Bytes`](bytes::Bytes)
+    /// chunks, writing and flushing each item into the pipe.
+    #[cfg(feature = "futures")]
+    pub fn into_sink(self) -> crate::SinkWriter {
+        crate::SinkWriter::new(self)
     }
 
     fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8]) -> Poll<io::Result<usize>> {
-        if Arc::strong_count(&self.state) == 1 {
+        let mut state = match self.state.lock() {
+            Ok(s) => s,
+            Err(err) => {
+                return Poll::Ready(Err(io::Error::other(
+                    format!(
+                        "{}: PipeWriter: Failed to lock the channel state: {}",
+                        env!("CARGO_PKG_NAME"),
+                        err
+                    ),
+                )))
+            }
+        };
+
+        if state.read_shutdown || state.reader_count == 0 {
             return Poll::Ready(Err(io::Error::new(
                 io::ErrorKind::BrokenPipe,
                 format!(
-                    "{}: PipeWriter: The channel is closed",
+                    "{}: PipeWriter: The reader half is closed",
                     env!("CARGO_PKG_NAME")
                 ),
             )));
         }
 
+        state.wake_readers();
+
+        if !state.bounded {
+            state.grow(buf.len());
+            let bytes_to_write = state.write(buf);
+            Poll::Ready(Ok(bytes_to_write))
+        } else if state.remaining() == 0 {
+            state.park_writer(cx.waker());
+            Poll::Pending
+        } else {
+            let bytes_to_write = state.write(buf);
+            Poll::Ready(Ok(bytes_to_write))
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
         let mut state = match self.state.lock() {
             Ok(s) => s,
             Err(err) => {
-                return Poll::Ready(Err(io::Error::new(
-                    io::ErrorKind::Other,
+                return Poll::Ready(Err(io::Error::other(
                     format!(
                         "{}: PipeWriter: Failed to lock the channel state: {}",
                         env!("CARGO_PKG_NAME"),
@@ -86,16 +137,36 @@ impl PipeWriter {
             }
         };
 
-        self.wake_reader_half(&*state);
+        if state.read_shutdown || state.reader_count == 0 {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                format!(
+                    "{}: PipeWriter: The reader half is closed",
+                    env!("CARGO_PKG_NAME")
+                ),
+            )));
+        }
+
+        state.wake_readers();
+
+        if !state.bounded {
+            let total = bufs.iter().map(|b| b.len()).sum();
+            state.grow(total);
+        }
+
+        let mut written = 0;
+        for buf in bufs {
+            if state.remaining() == 0 {
+                break;
+            }
+            written += state.write(&buf[..]);
+        }
 
-        let remaining = BUFFER_SIZE - state.buffer.len();
-        if remaining == 0 {
-            state.writer_waker = Some(cx.waker().clone());
+        if written == 0 && bufs.iter().any(|buf| !buf.is_empty()) {
+            state.park_writer(cx.waker());
             Poll::Pending
         } else {
-            let bytes_to_write = remaining.min(buf.len());
-            state.buffer.extend_from_slice(&buf[..bytes_to_write]);
-            Poll::Ready(Ok(bytes_to_write))
+            Poll::Ready(Ok(written))
         }
     }
 
@@ -103,8 +174,7 @@ impl PipeWriter {
         let mut state = match self.state.lock() {
             Ok(s) => s,
             Err(err) => {
-                return Poll::Ready(Err(io::Error::new(
-                    io::ErrorKind::Other,
+                return Poll::Ready(Err(io::Error::other(
                     format!(
                         "{}: PipeWriter: Failed to lock the channel state: {}",
                         env!("CARGO_PKG_NAME"),
@@ -114,11 +184,20 @@ impl PipeWriter {
             }
         };
 
-        if state.buffer.is_empty() {
+        if state.len == 0 {
             Poll::Ready(Ok(()))
+        } else if state.read_shutdown || state.reader_count == 0 {
+            // The reader is gone and will never drain the buffer, so the flush can never complete.
+            Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                format!(
+                    "{}: PipeWriter: The reader half is closed",
+                    env!("CARGO_PKG_NAME")
+                ),
+            )))
         } else {
-            state.writer_waker = Some(cx.waker().clone());
-            self.wake_reader_half(&*state);
+            state.park_writer(cx.waker());
+            state.wake_readers();
             Poll::Pending
         }
     }
@@ -126,8 +205,7 @@ impl PipeWriter {
     fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<io::Result<()>> {
         match self.close() {
             Ok(_) => Poll::Ready(Ok(())),
-            Err(err) => Poll::Ready(Err(io::Error::new(
-                io::ErrorKind::Other,
+            Err(err) => Poll::Ready(Err(io::Error::other(
                 format!(
                     "{}: PipeWriter: Failed to shutdown the channel: {}",
                     env!("CARGO_PKG_NAME"),
@@ -144,6 +222,18 @@ impl tokio::io::AsyncWrite for PipeWriter {
         self.poll_write(cx, buf)
     }
 
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
         self.poll_flush(cx)
     }
@@ -159,6 +249,14 @@ impl futures::io::AsyncWrite for PipeWriter {
         self.poll_write(cx, buf)
     }
 
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        self.poll_write_vectored(cx, bufs)
+    }
+
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
         self.poll_flush(cx)
     }
@@ -167,3 +265,33 @@ impl futures::io::AsyncWrite for PipeWriter {
         self.poll_shutdown(cx)
     }
 }
+
+impl Clone for PipeWriter {
+    fn clone(&self) -> PipeWriter {
+        // Recover from a poisoned lock so the count stays balanced against `Drop`.
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        state.writer_count += 1;
+        drop(state);
+
+        PipeWriter {
+            state: self.state.clone(),
+            // The clone is a fresh handle: it hasn't closed itself even if `self` has.
+            shutdown: Cell::new(false),
+        }
+    }
+}
+
+impl Drop for PipeWriter {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        state.writer_count -= 1;
+        if self.shutdown.get() {
+            // This half already counted towards `writer_shutdown_count`; leaving the live set
+            // means it shouldn't count towards it (or its siblings) anymore.
+            state.writer_shutdown_count -= 1;
+        }
+        // Either this was the last writer, or dropping it just made every writer still alive
+        // closed; either way the readers need to re-check for EOF.
+        state.wake_readers();
+    }
+}